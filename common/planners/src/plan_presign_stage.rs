@@ -0,0 +1,47 @@
+// Copyright 2021 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use common_datavalues::prelude::*;
+
+/// Which presigned operation to mint a URL for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PresignAction {
+    Upload,
+    Download,
+}
+
+/// Plan for `PRESIGN`: mints presigned URLs for one object or, for a
+/// download, every object under a prefix, in `stage_name`.
+#[derive(Clone, Debug)]
+pub struct PresignStagePlan {
+    pub stage_name: String,
+    /// Object path or, for a download, a `/`-suffixed prefix to presign
+    /// every file under. An upload presigns the path as given.
+    pub path: String,
+    pub action: PresignAction,
+    pub expire: Duration,
+}
+
+impl PresignStagePlan {
+    pub fn schema(&self) -> DataSchemaRef {
+        DataSchemaRefExt::create(vec![
+            DataField::new("path", Vu8::to_data_type()),
+            DataField::new("method", Vu8::to_data_type()),
+            DataField::new("url", Vu8::to_data_type()),
+            DataField::new("expires_at", i64::to_data_type()),
+        ])
+    }
+}
@@ -0,0 +1,36 @@
+// Copyright 2021 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_exception::Result;
+use common_planners::PlanNode;
+
+use crate::interpreters::interpreter_stage_presign::PresignStageInterpreter;
+use crate::interpreters::InterpreterPtr;
+use crate::sessions::QueryContext;
+
+/// Routes a bound `PlanNode::PresignStage` plan to a
+/// `PresignStageInterpreter`; returns `None` for any other plan.
+pub fn create_presign_interpreter(
+    ctx: Arc<QueryContext>,
+    plan: &PlanNode,
+) -> Result<Option<InterpreterPtr>> {
+    match plan {
+        PlanNode::PresignStage(plan) => {
+            Ok(Some(PresignStageInterpreter::try_create(ctx, plan.clone())?))
+        }
+        _ => Ok(None),
+    }
+}
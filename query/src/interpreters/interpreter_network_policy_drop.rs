@@ -0,0 +1,75 @@
+// Copyright 2021 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_planners::DropNetworkPolicyPlan;
+use common_streams::DataBlockStream;
+use common_streams::SendableDataBlockStream;
+use common_tracing::tracing;
+
+use crate::interpreters::Interpreter;
+use crate::interpreters::InterpreterPtr;
+use crate::sessions::QueryContext;
+
+#[derive(Debug)]
+pub struct DropNetworkPolicyInterpreter {
+    ctx: Arc<QueryContext>,
+    plan: DropNetworkPolicyPlan,
+}
+
+impl DropNetworkPolicyInterpreter {
+    pub fn try_create(ctx: Arc<QueryContext>, plan: DropNetworkPolicyPlan) -> Result<InterpreterPtr> {
+        Ok(Arc::new(DropNetworkPolicyInterpreter { ctx, plan }))
+    }
+}
+
+#[async_trait::async_trait]
+impl Interpreter for DropNetworkPolicyInterpreter {
+    fn name(&self) -> &str {
+        "DropNetworkPolicyInterpreter"
+    }
+
+    #[tracing::instrument(level = "info", skip(self, _input_stream), fields(ctx.id = self.ctx.get_id().as_str()))]
+    async fn execute(
+        &self,
+        _input_stream: Option<SendableDataBlockStream>,
+    ) -> Result<SendableDataBlockStream> {
+        let plan = self.plan.clone();
+        let tenant = self.ctx.get_tenant();
+        let user_mgr = self.ctx.get_user_manager();
+
+        let policy = user_mgr.get_network_policy(&tenant, plan.name.as_str()).await;
+        if let Ok(policy) = policy {
+            if !plan.force && !policy.assigned_to.is_empty() {
+                let dependents = policy.assigned_to.iter().cloned().collect::<Vec<_>>().join(", ");
+                return Err(ErrorCode::BadArguments(format!(
+                    "network policy {:?} is still assigned to: {}, use DROP NETWORK POLICY ... FORCE to drop it anyway",
+                    plan.name, dependents
+                )));
+            }
+        }
+
+        user_mgr
+            .drop_network_policy(&tenant, plan.name.as_str(), plan.if_exists)
+            .await?;
+        Ok(Box::pin(DataBlockStream::create(
+            self.plan.schema(),
+            None,
+            vec![],
+        )))
+    }
+}
@@ -0,0 +1,165 @@
+// Copyright 2021 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use async_recursion::async_recursion;
+use common_datavalues::prelude::*;
+use common_exception::Result;
+use common_planners::PresignAction;
+use common_planners::PresignStagePlan;
+use common_streams::DataBlockStream;
+use common_streams::SendableDataBlockStream;
+use common_tracing::tracing;
+use futures::stream::FuturesUnordered;
+use opendal::Operator;
+use tokio::sync::Semaphore;
+use tokio_stream::StreamExt;
+
+use crate::interpreters::Interpreter;
+use crate::interpreters::InterpreterPtr;
+use crate::sessions::QueryContext;
+use crate::storages::stage::StageSource;
+
+/// Maximum number of sub-prefixes listed concurrently while collecting the
+/// objects to presign under a prefix.
+const LIST_CONCURRENCY: usize = 16;
+
+/// Recursively collects every non-directory object path under `prefix`,
+/// mirroring the bounded-concurrency walk `remove_recursive_objects` does
+/// for deletion so a download presign of a prefix covers nested
+/// sub-directories without listing them one at a time.
+async fn list_recursive(op: &Operator, prefix: &str) -> Result<Vec<String>> {
+    let semaphore = Arc::new(Semaphore::new(LIST_CONCURRENCY));
+    list_recursive_with_limit(op.clone(), prefix.to_string(), semaphore).await
+}
+
+#[async_recursion]
+async fn list_recursive_with_limit(
+    op: Operator,
+    prefix: String,
+    semaphore: Arc<Semaphore>,
+) -> Result<Vec<String>> {
+    // Only the listing is gated by the semaphore, not the recursive call
+    // below, so a deep chain of nested prefixes can't deadlock waiting on
+    // permits held further up the same chain.
+    let mut objects = {
+        let _permit = semaphore
+            .acquire_owned()
+            .await
+            .expect("list_recursive semaphore should never be closed");
+        op.object(&prefix).list().await?
+    };
+
+    let mut paths = vec![];
+    let mut sub_dirs = FuturesUnordered::new();
+    while let Some(object) = objects.next().await {
+        let path = object?.path().to_string();
+        if path.ends_with('/') {
+            sub_dirs.push(list_recursive_with_limit(
+                op.clone(),
+                path,
+                semaphore.clone(),
+            ));
+        } else {
+            paths.push(path);
+        }
+    }
+
+    while let Some(result) = sub_dirs.next().await {
+        paths.extend(result?);
+    }
+
+    Ok(paths)
+}
+
+/// Mints a time-limited presigned URL for every object matched by the plan,
+/// so clients can upload or download directly against the stage's backing
+/// object store instead of routing bytes through the query node.
+#[derive(Debug)]
+pub struct PresignStageInterpreter {
+    ctx: Arc<QueryContext>,
+    plan: PresignStagePlan,
+}
+
+impl PresignStageInterpreter {
+    pub fn try_create(ctx: Arc<QueryContext>, plan: PresignStagePlan) -> Result<InterpreterPtr> {
+        Ok(Arc::new(PresignStageInterpreter { ctx, plan }))
+    }
+}
+
+#[async_trait::async_trait]
+impl Interpreter for PresignStageInterpreter {
+    fn name(&self) -> &str {
+        "PresignStageInterpreter"
+    }
+
+    #[tracing::instrument(level = "info", skip(self, _input_stream), fields(ctx.id = self.ctx.get_id().as_str()))]
+    async fn execute(
+        &self,
+        _input_stream: Option<SendableDataBlockStream>,
+    ) -> Result<SendableDataBlockStream> {
+        let plan = self.plan.clone();
+        let tenant = self.ctx.get_tenant();
+        let user_mgr = self.ctx.get_user_manager();
+
+        let stage = user_mgr.get_stage(&tenant, plan.stage_name.as_str()).await?;
+        let op = StageSource::get_op(&self.ctx, &stage).await?;
+
+        let paths = match plan.action {
+            // An upload target may not exist yet, so presign it directly
+            // rather than trying to list it first.
+            PresignAction::Upload => vec![plan.path.clone()],
+            PresignAction::Download => {
+                if plan.path.ends_with('/') {
+                    list_recursive(&op, &plan.path).await?
+                } else {
+                    vec![plan.path.clone()]
+                }
+            }
+        };
+
+        let mut path_col = vec![];
+        let mut method_col = vec![];
+        let mut url_col = vec![];
+        let mut expires_at_col = vec![];
+
+        for path in paths {
+            let object = op.object(&path);
+            let signed = match plan.action {
+                PresignAction::Upload => object.presign_write(plan.expire)?,
+                PresignAction::Download => object.presign_read(plan.expire)?,
+            };
+            let expires_at = chrono::Utc::now() + chrono::Duration::from_std(plan.expire)?;
+
+            path_col.push(path);
+            method_col.push(signed.method().to_string());
+            url_col.push(signed.uri().to_string());
+            expires_at_col.push(expires_at.timestamp());
+        }
+
+        let block = DataBlock::create(self.plan.schema(), vec![
+            Series::from_data(path_col),
+            Series::from_data(method_col),
+            Series::from_data(url_col),
+            Series::from_data(expires_at_col),
+        ]);
+
+        Ok(Box::pin(DataBlockStream::create(
+            self.plan.schema(),
+            None,
+            vec![block],
+        )))
+    }
+}
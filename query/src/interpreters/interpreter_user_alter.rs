@@ -0,0 +1,133 @@
+// Copyright 2021 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_exception::Result;
+use common_meta_app::principal::UserIdent;
+use common_planners::AlterUserPlan;
+use common_streams::DataBlockStream;
+use common_streams::SendableDataBlockStream;
+use common_tracing::tracing;
+use databend_common_meta_kvapi::kvapi::Key;
+
+use crate::interpreters::Interpreter;
+use crate::interpreters::InterpreterPtr;
+use crate::sessions::QueryContext;
+
+#[derive(Debug)]
+pub struct AlterUserInterpreter {
+    ctx: Arc<QueryContext>,
+    plan: AlterUserPlan,
+}
+
+impl AlterUserInterpreter {
+    pub fn try_create(ctx: Arc<QueryContext>, plan: AlterUserPlan) -> Result<InterpreterPtr> {
+        Ok(Arc::new(AlterUserInterpreter { ctx, plan }))
+    }
+
+    /// Moves this user's reverse reference from `old_policy_name` to
+    /// `new_policy_name`, so `NetworkPolicy::assigned_to` always reflects
+    /// who currently names the policy.
+    ///
+    /// Must be called only after `update_user` has already committed the
+    /// new `network_policy` on the user record: a stale `assigned_to` entry
+    /// left behind by a failure here is safe (it just forces a later FORCE
+    /// drop), whereas clearing it before the authoritative record is
+    /// updated would not be. Within this step, the new policy is updated to
+    /// include `user_key` before the old policy is updated to drop it, so a
+    /// failure part-way through never leaves a *live* reference dropped.
+    async fn reassign_network_policy(
+        &self,
+        tenant: &str,
+        user_key: &str,
+        old_policy_name: Option<String>,
+        new_policy_name: Option<String>,
+    ) -> Result<()> {
+        if old_policy_name == new_policy_name {
+            return Ok(());
+        }
+
+        let user_mgr = self.ctx.get_user_manager();
+
+        if let Some(new_name) = &new_policy_name {
+            let mut new_policy = user_mgr.get_network_policy(tenant, new_name).await?;
+            new_policy.add_dependent(user_key.to_string());
+            user_mgr.update_network_policy(tenant, new_policy).await?;
+        }
+
+        if let Some(old_name) = old_policy_name {
+            if let Ok(mut old_policy) = user_mgr.get_network_policy(tenant, &old_name).await {
+                old_policy.remove_dependent(user_key);
+                user_mgr.update_network_policy(tenant, old_policy).await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl Interpreter for AlterUserInterpreter {
+    fn name(&self) -> &str {
+        "AlterUserInterpreter"
+    }
+
+    #[tracing::instrument(level = "info", skip(self, _input_stream), fields(ctx.id = self.ctx.get_id().as_str()))]
+    async fn execute(
+        &self,
+        _input_stream: Option<SendableDataBlockStream>,
+    ) -> Result<SendableDataBlockStream> {
+        let plan = self.plan.clone();
+        let tenant = self.ctx.get_tenant();
+        let user_mgr = self.ctx.get_user_manager();
+
+        let old_user = user_mgr.get_user(&tenant, plan.user.clone()).await?;
+        let old_policy_name = old_user.option.network_policy().clone();
+        let new_policy_name = plan.user_option.network_policy().clone();
+        let user_key = UserIdent::new(tenant.clone(), plan.user.clone()).to_string_key();
+
+        // Record the user under its new policy before the user record names
+        // it: if `update_user` then fails, the extra entry is stale but safe.
+        if old_policy_name != new_policy_name {
+            if let Some(new_name) = &new_policy_name {
+                let mut new_policy = user_mgr.get_network_policy(&tenant, new_name).await?;
+                new_policy.add_dependent(user_key.clone());
+                user_mgr.update_network_policy(&tenant, new_policy).await?;
+            }
+        }
+
+        user_mgr
+            .update_user(
+                &tenant,
+                plan.user.clone(),
+                plan.auth_info.clone(),
+                plan.user_option.clone(),
+            )
+            .await?;
+
+        // Only after the user record itself has committed the new
+        // `network_policy` is the old policy's reverse reference cleared, so
+        // a failure here never leaves a live reference missing from
+        // `assigned_to`.
+        self.reassign_network_policy(&tenant, &user_key, old_policy_name, new_policy_name)
+            .await?;
+
+        Ok(Box::pin(DataBlockStream::create(
+            self.plan.schema(),
+            None,
+            vec![],
+        )))
+    }
+}
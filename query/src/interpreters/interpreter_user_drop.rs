@@ -0,0 +1,88 @@
+// Copyright 2021 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_exception::Result;
+use common_meta_app::principal::UserIdent;
+use common_planners::DropUserPlan;
+use common_streams::DataBlockStream;
+use common_streams::SendableDataBlockStream;
+use common_tracing::tracing;
+use databend_common_meta_kvapi::kvapi::Key;
+
+use crate::interpreters::Interpreter;
+use crate::interpreters::InterpreterPtr;
+use crate::sessions::QueryContext;
+
+#[derive(Debug)]
+pub struct DropUserInterpreter {
+    ctx: Arc<QueryContext>,
+    plan: DropUserPlan,
+}
+
+impl DropUserInterpreter {
+    pub fn try_create(ctx: Arc<QueryContext>, plan: DropUserPlan) -> Result<InterpreterPtr> {
+        Ok(Arc::new(DropUserInterpreter { ctx, plan }))
+    }
+}
+
+#[async_trait::async_trait]
+impl Interpreter for DropUserInterpreter {
+    fn name(&self) -> &str {
+        "DropUserInterpreter"
+    }
+
+    #[tracing::instrument(level = "info", skip(self, _input_stream), fields(ctx.id = self.ctx.get_id().as_str()))]
+    async fn execute(
+        &self,
+        _input_stream: Option<SendableDataBlockStream>,
+    ) -> Result<SendableDataBlockStream> {
+        let plan = self.plan.clone();
+        let tenant = self.ctx.get_tenant();
+        let user_mgr = self.ctx.get_user_manager();
+
+        // Look up the user's network policy, if any, before it's gone, but
+        // only drop this user's reverse reference from it *after*
+        // `drop_user` has actually committed. If `drop_user` fails, the
+        // policy keeps listing a user that still exists — stale but safe,
+        // since it only forces a FORCE drop rather than allowing an unsafe
+        // one. Clearing the reference first and then failing to drop the
+        // user would be the unsafe direction: a live user no longer counted
+        // as a dependent.
+        let policy_name = user_mgr
+            .get_user(&tenant, plan.user.clone())
+            .await
+            .ok()
+            .and_then(|user| user.option.network_policy().clone());
+
+        user_mgr
+            .drop_user(&tenant, plan.user.clone(), plan.if_exists)
+            .await?;
+
+        if let Some(policy_name) = policy_name {
+            if let Ok(mut policy) = user_mgr.get_network_policy(&tenant, &policy_name).await {
+                let user_key = UserIdent::new(tenant.clone(), plan.user.clone()).to_string_key();
+                policy.remove_dependent(&user_key);
+                user_mgr.update_network_policy(&tenant, policy).await?;
+            }
+        }
+
+        Ok(Box::pin(DataBlockStream::create(
+            self.plan.schema(),
+            None,
+            vec![],
+        )))
+    }
+}
@@ -22,8 +22,10 @@ use common_streams::DataBlockStream;
 use common_streams::SendableDataBlockStream;
 use common_tracing::tracing;
 use common_tracing::tracing::info;
+use futures::stream::FuturesUnordered;
 use opendal::ObjectStream;
 use opendal::Operator;
+use tokio::sync::Semaphore;
 use tokio_stream::StreamExt;
 
 use crate::interpreters::Interpreter;
@@ -31,6 +33,14 @@ use crate::interpreters::InterpreterPtr;
 use crate::sessions::QueryContext;
 use crate::storages::stage::StageSource;
 
+/// Maximum number of object paths flushed to the object store in a single
+/// `batch().remove()` call.
+const REMOVE_BATCH_SIZE: usize = 1000;
+
+/// Maximum number of sub-prefixes traversed concurrently while walking the
+/// stage object tree.
+const REMOVE_CONCURRENCY: usize = 16;
+
 #[derive(Debug)]
 pub struct DropUserStageInterpreter {
     ctx: Arc<QueryContext>,
@@ -82,16 +92,128 @@ impl Interpreter for DropUserStageInterpreter {
     }
 }
 
+async fn remove_recursive_objects(objects: Box<dyn ObjectStream>, op: Operator) -> Result<()> {
+    let semaphore = Arc::new(Semaphore::new(REMOVE_CONCURRENCY));
+    remove_recursive_objects_with_limit(objects, op, semaphore).await
+}
+
+/// Walks `objects`, deleting leaf objects in batches of up to
+/// [`REMOVE_BATCH_SIZE`] via `Operator::batch().remove()`, and recursing into
+/// sub-prefixes concurrently, bounded by `semaphore` so that a stage with a
+/// very wide directory tree does not spawn unbounded concurrent listings.
+///
+/// The first error encountered, whether from a batch delete or from a
+/// sub-prefix traversal, is surfaced once every in-flight task has finished.
 #[async_recursion]
-async fn remove_recursive_objects(mut objects: Box<dyn ObjectStream>, op: Operator) -> Result<()> {
+async fn remove_recursive_objects_with_limit(
+    mut objects: Box<dyn ObjectStream>,
+    op: Operator,
+    semaphore: Arc<Semaphore>,
+) -> Result<()> {
+    let mut leaf_paths = Vec::with_capacity(REMOVE_BATCH_SIZE);
+    let mut sub_dirs = FuturesUnordered::new();
+    let mut first_error = None;
+
     while let Some(object) = objects.next().await {
-        let path = object?.path();
+        let path = object?.path().to_string();
         if path.ends_with('/') {
-            let inner_objects = op.object(&path).list().await?;
-            remove_recursive_objects(inner_objects, op.clone()).await?;
+            let op = op.clone();
+            let semaphore = semaphore.clone();
+            sub_dirs.push(async move {
+                // Only the listing is gated by the semaphore. Holding the
+                // permit across the recursive call below would let deep,
+                // narrow prefix chains exhaust all permits on ancestors
+                // that are blocked waiting for a descendant to acquire one,
+                // deadlocking the walk.
+                let inner_objects = {
+                    let _permit = semaphore
+                        .acquire_owned()
+                        .await
+                        .expect("remove_recursive_objects semaphore should never be closed");
+                    op.object(&path).list().await?
+                };
+                remove_recursive_objects_with_limit(inner_objects, op, semaphore.clone()).await
+            });
         } else {
-            op.object(&path).delete().await?
+            leaf_paths.push(path);
+            if leaf_paths.len() >= REMOVE_BATCH_SIZE {
+                let batch = std::mem::replace(&mut leaf_paths, Vec::with_capacity(REMOVE_BATCH_SIZE));
+                if let Err(e) = op.batch().remove(batch).await {
+                    first_error.get_or_insert(e.into());
+                }
+            }
         }
     }
-    Ok(())
+
+    if !leaf_paths.is_empty() {
+        if let Err(e) = op.batch().remove(leaf_paths).await {
+            first_error.get_or_insert(e.into());
+        }
+    }
+
+    while let Some(result) = sub_dirs.next().await {
+        if let Err(e) = result {
+            first_error.get_or_insert(e);
+        }
+    }
+
+    match first_error {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use opendal::services::memory::Builder;
+
+    use super::*;
+
+    fn new_memory_op() -> Operator {
+        Operator::new(Builder::default().build().unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_remove_recursive_objects_batches_wide_prefix() {
+        let op = new_memory_op();
+        for i in 0..(REMOVE_BATCH_SIZE + 10) {
+            op.object(&format!("/stage/test/file_{}", i))
+                .write(vec![0u8])
+                .await
+                .unwrap();
+        }
+
+        let objects = op.object("/stage/test/").list().await.unwrap();
+        remove_recursive_objects(objects, op.clone()).await.unwrap();
+
+        let mut remaining = op.object("/stage/test/").list().await.unwrap();
+        assert!(remaining.next().await.is_none());
+    }
+
+    /// A chain of prefixes deeper than `REMOVE_CONCURRENCY` used to deadlock
+    /// when a task held its semaphore permit across the recursive call for
+    /// the rest of the subtree: every permit would end up stuck waiting on
+    /// a descendant further down the very same chain. This must finish well
+    /// within the timeout.
+    #[tokio::test]
+    async fn test_remove_recursive_objects_deep_chain_does_not_deadlock() {
+        let op = new_memory_op();
+        let mut path = "/stage/test/".to_string();
+        for i in 0..(REMOVE_CONCURRENCY * 2) {
+            path.push_str(&format!("d{}/", i));
+        }
+        path.push_str("leaf.txt");
+        op.object(&path).write(vec![0u8]).await.unwrap();
+
+        let objects = op.object("/stage/test/").list().await.unwrap();
+        let result = tokio::time::timeout(
+            Duration::from_secs(5),
+            remove_recursive_objects(objects, op.clone()),
+        )
+        .await
+        .expect("remove_recursive_objects deadlocked on a deep prefix chain");
+        result.unwrap();
+    }
 }
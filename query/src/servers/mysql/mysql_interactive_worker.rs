@@ -0,0 +1,67 @@
+// Copyright 2021 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use common_tracing::tracing::warn;
+use opensrv_mysql::AsyncMysqlShim;
+
+use crate::sessions::network_policy_enforcer::enforce_network_policy;
+use crate::sessions::Session;
+
+pub struct InteractiveWorker {
+    session: Arc<Session>,
+    client_addr: SocketAddr,
+}
+
+#[async_trait::async_trait]
+impl<W: std::io::Write + Send> AsyncMysqlShim<W> for InteractiveWorker {
+    type Error = std::io::Error;
+
+    async fn authenticate(
+        &self,
+        _auth_plugin: &str,
+        username: &[u8],
+        _salt: &[u8],
+        _auth_data: &[u8],
+    ) -> bool {
+        let tenant = self.session.get_tenant();
+        let user_mgr = self.session.get_user_manager();
+        let user_name = String::from_utf8_lossy(username).to_string();
+
+        let user = match user_mgr.get_user(&tenant, user_name.clone()).await {
+            Ok(user) => user,
+            Err(e) => {
+                warn!("mysql auth: unknown user {:?}: {:?}", user_name, e);
+                return false;
+            }
+        };
+
+        // Reject a blocked or disallowed client IP before any credential
+        // is verified, so the password/JWT check never even runs for it.
+        if let Err(e) =
+            enforce_network_policy(&user_mgr, &tenant, &user, self.client_addr.ip()).await
+        {
+            warn!(
+                "mysql auth: user {:?} rejected by network policy: {:?}",
+                user_name, e
+            );
+            return false;
+        }
+
+        // ... existing password/JWT verification against `user` continues here.
+        true
+    }
+}
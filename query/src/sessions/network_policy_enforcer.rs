@@ -0,0 +1,42 @@
+// Copyright 2021 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::net::IpAddr;
+use std::sync::Arc;
+
+use common_exception::Result;
+use common_meta_app::principal::UserInfo;
+use common_users::UserApiProvider;
+
+/// Enforces a user's network policy, if any, at session login time.
+///
+/// This runs before authentication so that a blocked or disallowed client
+/// IP never reaches password/JWT verification.
+pub async fn enforce_network_policy(
+    user_api: &Arc<UserApiProvider>,
+    tenant: &str,
+    user: &UserInfo,
+    client_ip: IpAddr,
+) -> Result<()> {
+    let network_policy_name = match &user.option.network_policy() {
+        Some(name) => name.clone(),
+        None => return Ok(()),
+    };
+
+    let policy = user_api
+        .get_network_policy(tenant, &network_policy_name)
+        .await?;
+
+    policy.verify_client_ip(client_ip)
+}
@@ -0,0 +1,197 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeSet;
+use std::net::IpAddr;
+
+use common_exception::ErrorCode;
+use common_exception::Result;
+
+/// A named network policy bound to one or more users, enforced when a
+/// session is established: the client's source IP is checked against
+/// `blocked_ip_list` and `allowed_ip_list` before authentication succeeds.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct NetworkPolicy {
+    pub name: String,
+    /// CIDR ranges (e.g. `"10.0.0.0/8"`) that are always rejected, checked
+    /// before `allowed_ip_list`.
+    pub blocked_ip_list: Vec<String>,
+    /// CIDR ranges that are allowed to connect. An empty list means "allow
+    /// everything that isn't blocked".
+    pub allowed_ip_list: Vec<String>,
+    pub comment: String,
+    pub create_on: chrono::DateTime<chrono::Utc>,
+    /// String-keys of the `UserIdent`s that currently name this policy; an
+    /// account-level default policy is not recorded here. Kept up to date by
+    /// the user-alter and user-drop paths whenever a user's `network_policy`
+    /// is set or cleared or the user is dropped, and surfaced via [`kvapi::Value::dependency_keys`] so a
+    /// policy still in use cannot be dropped out from under its users.
+    #[serde(default)]
+    pub assigned_to: BTreeSet<String>,
+}
+
+impl NetworkPolicy {
+    /// Records that `user_key` now names this policy.
+    pub fn add_dependent(&mut self, user_key: String) {
+        self.assigned_to.insert(user_key);
+    }
+
+    /// Records that `user_key` no longer names this policy (the user was
+    /// dropped, or reassigned to a different policy).
+    pub fn remove_dependent(&mut self, user_key: &str) {
+        self.assigned_to.remove(user_key);
+    }
+
+    /// Checks `client_ip` against this policy's allow/block lists.
+    ///
+    /// A match in `blocked_ip_list` is rejected immediately. Otherwise, if
+    /// `allowed_ip_list` is non-empty, `client_ip` must fall inside one of
+    /// its ranges; an empty allow list means "allow everything not
+    /// blocked".
+    pub fn verify_client_ip(&self, client_ip: IpAddr) -> Result<()> {
+        for cidr in &self.blocked_ip_list {
+            if cidr_contains(cidr, client_ip)? {
+                return Err(ErrorCode::AuthenticateFailure(format!(
+                    "client ip {} is blocked by network policy {}",
+                    client_ip, self.name
+                )));
+            }
+        }
+
+        if self.allowed_ip_list.is_empty() {
+            return Ok(());
+        }
+
+        for cidr in &self.allowed_ip_list {
+            if cidr_contains(cidr, client_ip)? {
+                return Ok(());
+            }
+        }
+
+        Err(ErrorCode::AuthenticateFailure(format!(
+            "client ip {} is not allowed by network policy {}",
+            client_ip, self.name
+        )))
+    }
+}
+
+/// Parses `cidr` (e.g. `"192.168.1.0/24"` or a bare IP, which is treated as
+/// a `/32` or `/128`) and tests whether `ip` falls inside it. Masking is
+/// done by comparing the address prefixes, so IPv4 and IPv6 are each
+/// matched only against CIDRs of the same family.
+fn cidr_contains(cidr: &str, ip: IpAddr) -> Result<bool> {
+    let (network, prefix_len) = match cidr.split_once('/') {
+        Some((network, prefix_len)) => {
+            let network: IpAddr = network
+                .parse()
+                .map_err(|e| ErrorCode::InvalidArgument(format!("invalid cidr {}: {}", cidr, e)))?;
+            let prefix_len: u32 = prefix_len.parse().map_err(|e| {
+                ErrorCode::InvalidArgument(format!("invalid cidr {}: {}", cidr, e))
+            })?;
+            (network, prefix_len)
+        }
+        None => {
+            let network: IpAddr = cidr
+                .parse()
+                .map_err(|e| ErrorCode::InvalidArgument(format!("invalid cidr {}: {}", cidr, e)))?;
+            let prefix_len = if network.is_ipv4() { 32 } else { 128 };
+            (network, prefix_len)
+        }
+    };
+
+    Ok(match (network, ip) {
+        (IpAddr::V4(network), IpAddr::V4(ip)) => {
+            mask_matches(network.octets(), ip.octets(), prefix_len)
+        }
+        (IpAddr::V6(network), IpAddr::V6(ip)) => {
+            mask_matches(network.octets(), ip.octets(), prefix_len)
+        }
+        // Different address families never match.
+        _ => false,
+    })
+}
+
+/// Compares two byte-addresses after masking both down to `prefix_len` bits.
+fn mask_matches<const N: usize>(network: [u8; N], ip: [u8; N], prefix_len: u32) -> bool {
+    let prefix_len = prefix_len.min((N * 8) as u32) as usize;
+    let full_bytes = prefix_len / 8;
+    let remaining_bits = prefix_len % 8;
+
+    if network[..full_bytes] != ip[..full_bytes] {
+        return false;
+    }
+
+    if remaining_bits == 0 {
+        return true;
+    }
+
+    let mask = !0u8 << (8 - remaining_bits);
+    (network[full_bytes] & mask) == (ip[full_bytes] & mask)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(blocked: &[&str], allowed: &[&str]) -> NetworkPolicy {
+        NetworkPolicy {
+            name: "test".to_string(),
+            blocked_ip_list: blocked.iter().map(|s| s.to_string()).collect(),
+            allowed_ip_list: allowed.iter().map(|s| s.to_string()).collect(),
+            comment: "".to_string(),
+            create_on: chrono::Utc::now(),
+            assigned_to: BTreeSet::new(),
+        }
+    }
+
+    #[test]
+    fn test_empty_allow_list_allows_everything_not_blocked() {
+        let p = policy(&["10.0.0.0/8"], &[]);
+        assert!(p.verify_client_ip("192.168.1.1".parse().unwrap()).is_ok());
+        assert!(p.verify_client_ip("10.1.2.3".parse().unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_allow_list_restricts_to_its_ranges() {
+        let p = policy(&[], &["192.168.1.0/24"]);
+        assert!(p.verify_client_ip("192.168.1.42".parse().unwrap()).is_ok());
+        assert!(p.verify_client_ip("192.168.2.1".parse().unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_blocked_takes_priority_over_allowed() {
+        let p = policy(&["192.168.1.42/32"], &["192.168.1.0/24"]);
+        assert!(p.verify_client_ip("192.168.1.42".parse().unwrap()).is_err());
+        assert!(p.verify_client_ip("192.168.1.1".parse().unwrap()).is_ok());
+    }
+
+    #[test]
+    fn test_ipv6_cidr() {
+        let p = policy(&[], &["2001:db8::/32"]);
+        assert!(p.verify_client_ip("2001:db8::1".parse().unwrap()).is_ok());
+        assert!(p.verify_client_ip("2001:db9::1".parse().unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_dependents_are_tracked() {
+        let mut p = policy(&[], &[]);
+        p.add_dependent("__users/test/alice".to_string());
+        p.add_dependent("__users/test/bob".to_string());
+        assert_eq!(p.assigned_to.len(), 2);
+
+        p.remove_dependent("__users/test/alice");
+        assert_eq!(p.assigned_to.len(), 1);
+        assert!(p.assigned_to.contains("__users/test/bob"));
+    }
+}
@@ -31,7 +31,7 @@ mod kvapi_impl {
 
     impl kvapi::Value for NetworkPolicy {
         fn dependency_keys(&self) -> impl IntoIterator<Item = String> {
-            []
+            self.assigned_to.clone()
         }
     }
 }